@@ -1,14 +1,23 @@
+use std::collections::BTreeSet;
 use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::{BufRead, BufReader, Write};
 use std::num::NonZeroUsize;
 use std::ops::Range;
 
 use clap::{Args, Parser};
-use csv::{ReaderBuilder, StringRecord, WriterBuilder};
+use csv::{ReaderBuilder, StringRecord, Terminator, WriterBuilder};
 use regex::Regex;
 
 pub type MyResult<T> = Result<T, Box<dyn std::error::Error>>;
-type PositionList = Vec<Range<usize>>;
+
+#[derive(Debug, PartialEq, Clone)]
+enum PosRange {
+    Closed(Range<usize>),
+    From(usize),
+    To(usize),
+}
+
+type PositionList = Vec<PosRange>;
 
 fn parse_index(input: &str) -> Result<usize, String> {
     let value_error = || format!("illegal list value: \"{}\"", input);
@@ -24,36 +33,56 @@ fn parse_index(input: &str) -> Result<usize, String> {
 }
 
 fn parse_pos(range: &str) -> Result<PositionList, String> {
-    let range_re = Regex::new(r"^(\d+)-(\d+)$").unwrap();
+    let range_re = Regex::new(r"^(\d+)?-(\d+)?$").unwrap();
 
     range.split(',')
-        .into_iter()
         .map(|val| {
             parse_index(val)
-                .map(|n| n..n + 1)
+                .map(|n| PosRange::Closed(n..n + 1))
                 .or_else(|e| {
                     range_re.captures(val).ok_or(e).and_then(|captures| {
-                        let n1 = parse_index(&captures[1])?;
-                        let n2 = parse_index(&captures[2])?;
-                        if n1 >= n2 {
-                            return Err(format!("First number in range ({}) must be lower than second number ({})", n1 + 1, n2 + 1));
+                        let start = captures.get(1).map(|m| m.as_str());
+                        let end = captures.get(2).map(|m| m.as_str());
+
+                        match (start, end) {
+                            (Some(n1), Some(n2)) => {
+                                let n1 = parse_index(n1)?;
+                                let n2 = parse_index(n2)?;
+                                if n1 >= n2 {
+                                    return Err(format!("First number in range ({}) must be lower than second number ({})", n1 + 1, n2 + 1));
+                                }
+
+                                Ok(PosRange::Closed(n1..n2 + 1))
+                            }
+                            (Some(n1), None) => parse_index(n1).map(PosRange::From),
+                            (None, Some(n2)) => parse_index(n2).map(|n| PosRange::To(n + 1)),
+                            (None, None) => Err(format!("illegal list value: \"{}\"", val)),
                         }
-
-                        Ok(n1..n2 + 1)
                     })
                 })
-        }).collect::<Result<_, _>>()
-        .map_err(|e| e.into())
+        }).collect()
+}
+
+fn parse_regex_delim(input: &str) -> Result<Regex, String> {
+    Regex::new(input).map_err(|e| e.to_string())
+}
+
+fn single_delimiter_byte(delimiter: &str) -> Result<u8, String> {
+    let bytes = delimiter.as_bytes();
+    match bytes {
+        [byte] => Ok(*byte),
+        _ => Err(format!("--delim \"{}\" must be a single byte", delimiter)),
+    }
 }
 
 #[derive(Args, Debug)]
 #[group(required = true, multiple = false)]
 struct Extract {
-    #[arg(short, long, help = "Selected fields", value_parser = parse_pos)]
+    #[arg(short, long, help = "Selected fields", value_parser = parse_pos, allow_hyphen_values = true)]
     fields: Option<PositionList>,
-    #[arg(short, long, help = "Selected bytes", value_parser = parse_pos)]
+    #[arg(short, long, help = "Selected bytes", value_parser = parse_pos, allow_hyphen_values = true)]
     bytes: Option<PositionList>,
-    #[arg(short, long, help = "Selected characters", value_parser = parse_pos)]
+    #[arg(short, long, help = "Selected characters", value_parser = parse_pos, allow_hyphen_values = true)]
     chars: Option<PositionList>,
 }
 
@@ -68,7 +97,17 @@ pub struct Cli {
     #[arg(value_name = "FILE", help = "Input file(s)", default_value = "-")]
     files: Vec<String>,
     #[arg(short, long = "delim", help = "Field delimiter", default_value = "\t")]
-    delimiter: char,
+    delimiter: String,
+    #[arg(long, help = "Complement the set of selected bytes, characters or fields")]
+    complement: bool,
+    #[arg(long, help = "Use STRING as the output field delimiter (defaults to the input delimiter, or a tab when -r/--regex-delim is set)")]
+    output_delimiter: Option<String>,
+    #[arg(short = 'z', long = "zero-terminated", help = "Line delimiter is NUL, not newline")]
+    zero_terminated: bool,
+    #[arg(short = 'r', long = "regex-delim", help = "Interpret -d/--delim as a regex instead of a literal delimiter character")]
+    regex_delim: bool,
+    #[arg(short = 's', long = "only-delimited", help = "Suppress lines with no field delimiter")]
+    only_delimited: bool,
 }
 
 pub fn get_args() -> MyResult<Cli> {
@@ -82,41 +121,144 @@ fn open(filename: &str) -> MyResult<Box<dyn BufRead>> {
     }
 }
 
-fn extract_chars(line: &str, char_pos: &[Range<usize>]) -> String {
+fn read_lines(
+    mut reader: Box<dyn BufRead>,
+    terminator: u8,
+) -> impl Iterator<Item = MyResult<String>> {
+    std::iter::from_fn(move || {
+        let mut buf = Vec::new();
+        match reader.read_until(terminator, &mut buf) {
+            Ok(0) => None,
+            Ok(_) => {
+                if buf.last() == Some(&terminator) {
+                    buf.pop();
+                }
+
+                Some(String::from_utf8(buf).map_err(|e| e.into()))
+            }
+            Err(e) => Some(Err(e.into())),
+        }
+    })
+}
+
+fn read_byte_lines(
+    mut reader: Box<dyn BufRead>,
+    terminator: u8,
+) -> impl Iterator<Item = MyResult<Vec<u8>>> {
+    std::iter::from_fn(move || {
+        let mut buf = Vec::new();
+        match reader.read_until(terminator, &mut buf) {
+            Ok(0) => None,
+            Ok(_) => {
+                if buf.last() == Some(&terminator) {
+                    buf.pop();
+                }
+
+                Some(Ok(buf))
+            }
+            Err(e) => Some(Err(e.into())),
+        }
+    })
+}
+
+fn selected_indices(pos_list: &[PosRange], len: usize) -> BTreeSet<usize> {
+    let mut indices = BTreeSet::new();
+    for pos in pos_list {
+        match pos {
+            PosRange::Closed(r) => indices.extend(r.start..r.end.min(len)),
+            PosRange::From(start) => indices.extend(*start..len),
+            PosRange::To(end) => indices.extend(0..(*end).min(len)),
+        }
+    }
+
+    indices
+}
+
+fn extract_chars(line: &str, char_pos: &[PosRange], complement: bool) -> String {
+    if complement {
+        let chars: Vec<char> = line.chars().collect();
+        let selected = selected_indices(char_pos, chars.len());
+        return chars.into_iter()
+            .enumerate()
+            .filter(|(i, _)| !selected.contains(i))
+            .map(|(_, c)| c)
+            .collect();
+    }
+
     let mut str = String::new();
     for pos in char_pos {
-        line.chars()
-            .skip(pos.start)
-            .take(pos.end - pos.start)
-            .for_each(|c| str.push(c));
+        match pos {
+            PosRange::Closed(r) => line.chars()
+                .skip(r.start)
+                .take(r.end - r.start)
+                .for_each(|c| str.push(c)),
+            PosRange::From(start) => line.chars()
+                .skip(*start)
+                .for_each(|c| str.push(c)),
+            PosRange::To(end) => line.chars()
+                .take(*end)
+                .for_each(|c| str.push(c)),
+        }
     }
 
     str
 }
 
-fn extract_bytes(line: &str, byte_pos: &[Range<usize>]) -> String {
-    let mut bytes: Vec<u8> = vec![];
+fn extract_bytes_raw(bytes: &[u8], byte_pos: &[PosRange], complement: bool) -> Vec<u8> {
+    if complement {
+        let selected = selected_indices(byte_pos, bytes.len());
+        return bytes.iter()
+            .enumerate()
+            .filter(|(i, _)| !selected.contains(i))
+            .map(|(_, b)| *b)
+            .collect();
+    }
+
+    let mut out: Vec<u8> = vec![];
     for pos in byte_pos {
-        line.bytes()
-            .skip(pos.start)
-            .take(pos.end - pos.start)
-            .for_each(|b| bytes.push(b));
+        match pos {
+            PosRange::Closed(r) => out.extend(bytes.iter().skip(r.start).take(r.end - r.start)),
+            PosRange::From(start) => out.extend(bytes.iter().skip(*start)),
+            PosRange::To(end) => out.extend(bytes.iter().take(*end)),
+        }
     }
 
-    String::from_utf8_lossy(&bytes).to_string()
+    out
+}
+
+fn is_delimited(record: &StringRecord) -> bool {
+    record.len() > 1
 }
 
 fn extract_fields(
     record: &StringRecord,
-    field_pos: &[Range<usize>],
+    field_pos: &[PosRange],
+    complement: bool,
 ) -> Vec<String> {
+    if complement {
+        let selected = selected_indices(field_pos, record.len());
+        return record.iter()
+            .enumerate()
+            .filter(|(i, _)| !selected.contains(i))
+            .map(|(_, s)| s.to_string())
+            .collect();
+    }
+
     let mut fields: Vec<String> = vec![];
 
     for pos in field_pos {
-        record.iter()
-            .skip(pos.start)
-            .take(pos.end - pos.start)
-            .for_each(|s| fields.push(s.to_string()));
+        match pos {
+            PosRange::Closed(r) => record.iter()
+                .skip(r.start)
+                .take(r.end - r.start)
+                .for_each(|s| fields.push(s.to_string())),
+            PosRange::From(start) => record.iter()
+                .skip(*start)
+                .for_each(|s| fields.push(s.to_string())),
+            PosRange::To(end) => record.iter()
+                .take(*end)
+                .for_each(|s| fields.push(s.to_string())),
+        }
     }
 
     fields
@@ -127,30 +269,80 @@ pub fn run(cli: Cli) -> MyResult<()> {
         match open(filename) {
             Err(e) => eprintln!("{}: {}", filename, e),
             Ok(f) => {
+                let terminator = if cli.zero_terminated { b'\0' } else { b'\n' };
+                let line_ending = terminator as char;
+
                 if let Some(ref position_list) = cli.extract.chars {
-                    for line in f.lines() {
+                    for line in read_lines(f, terminator) {
                         let line = line?;
-                        println!("{}", extract_chars(&line, &position_list));
+                        print!("{}{}", extract_chars(&line, position_list, cli.complement), line_ending);
                     }
                 } else if let Some(ref position_list) = cli.extract.bytes {
-                    for line in f.lines() {
+                    let stdout = std::io::stdout();
+                    let mut out = stdout.lock();
+
+                    for line in read_byte_lines(f, terminator) {
                         let line = line?;
-                        println!("{}", extract_bytes(&line, &position_list));
+                        out.write_all(&extract_bytes_raw(&line, position_list, cli.complement))?;
+                        out.write_all(&[terminator])?;
                     }
                 } else if let Some(ref position_list) = cli.extract.fields {
-                    let mut rdr = ReaderBuilder::new()
-                        .has_headers(false)
-                        .delimiter(cli.delimiter as u8)
-                        .from_reader(f);
-
-                    let mut wtr = WriterBuilder::new()
-                        .delimiter(cli.delimiter as u8)
-                        .from_writer(std::io::stdout());
-
-                    for record in rdr.records() {
-                        let record = record?;
-                        let fields = extract_fields(&record, &position_list);
-                        wtr.write_record(fields.iter())?;
+                    let output_delimiter = cli.output_delimiter.clone().unwrap_or_else(|| {
+                        if cli.regex_delim {
+                            "\t".to_string()
+                        } else {
+                            cli.delimiter.clone()
+                        }
+                    });
+
+                    if cli.regex_delim {
+                        let re = parse_regex_delim(&cli.delimiter)?;
+                        for line in read_lines(f, terminator) {
+                            let line = line?;
+                            let record = StringRecord::from(re.split(&line).collect::<Vec<_>>());
+                            if cli.only_delimited && !is_delimited(&record) {
+                                continue;
+                            }
+
+                            let fields = extract_fields(&record, position_list, cli.complement);
+                            print!("{}{}", fields.join(&output_delimiter), line_ending);
+                        }
+                    } else {
+                        let delimiter = single_delimiter_byte(&cli.delimiter)?;
+                        let mut rdr = ReaderBuilder::new()
+                            .has_headers(false)
+                            .delimiter(delimiter)
+                            .terminator(Terminator::Any(terminator))
+                            .flexible(true)
+                            .from_reader(f);
+
+                        if let [byte] = output_delimiter.as_bytes() {
+                            let mut wtr = WriterBuilder::new()
+                                .delimiter(*byte)
+                                .terminator(Terminator::Any(terminator))
+                                .flexible(true)
+                                .from_writer(std::io::stdout());
+
+                            for record in rdr.records() {
+                                let record = record?;
+                                if cli.only_delimited && !is_delimited(&record) {
+                                    continue;
+                                }
+
+                                let fields = extract_fields(&record, position_list, cli.complement);
+                                wtr.write_record(fields.iter())?;
+                            }
+                        } else {
+                            for record in rdr.records() {
+                                let record = record?;
+                                if cli.only_delimited && !is_delimited(&record) {
+                                    continue;
+                                }
+
+                                let fields = extract_fields(&record, position_list, cli.complement);
+                                print!("{}{}", fields.join(&output_delimiter), line_ending);
+                            }
+                        }
                     }
                 } else {
                     unimplemented!()
@@ -166,6 +358,65 @@ pub fn run(cli: Cli) -> MyResult<()> {
 mod unit_tests {
     use super::*;
 
+    #[test]
+    fn test_single_delimiter_byte() {
+        assert_eq!(single_delimiter_byte(":"), Ok(b':'));
+        assert!(single_delimiter_byte("").is_err());
+        assert!(single_delimiter_byte("::").is_err());
+    }
+
+    #[test]
+    fn test_parse_regex_delim() {
+        assert!(parse_regex_delim(r"\s+").is_ok());
+        assert!(parse_regex_delim(r",|;").is_ok());
+        assert!(parse_regex_delim(r"(").is_err());
+    }
+
+    #[test]
+    fn test_regex_delim_split_fields() {
+        let re = parse_regex_delim(r"\s+").unwrap();
+        let record = StringRecord::from(re.split("foo   bar  baz").collect::<Vec<_>>());
+        assert_eq!(
+            extract_fields(&record, &[PosRange::Closed(0..1), PosRange::Closed(2..3)], false),
+            &["foo", "baz"]
+        );
+
+        let re = parse_regex_delim(r",|;").unwrap();
+        let record = StringRecord::from(re.split("a,b;c").collect::<Vec<_>>());
+        assert_eq!(
+            extract_fields(&record, &[PosRange::Closed(0..3)], false),
+            &["a", "b", "c"]
+        );
+    }
+
+    #[test]
+    fn test_regex_delim_output_does_not_reuse_the_pattern_as_a_delimiter() {
+        // cutr -r -d '\s+' -f 1,3
+        let cli = Cli::try_parse_from(["cutr", "-r", "-d", r"\s+", "-f", "1,3", "-"]).unwrap();
+        let output_delimiter = cli.output_delimiter.clone().unwrap_or_else(|| {
+            if cli.regex_delim {
+                "\t".to_string()
+            } else {
+                cli.delimiter.clone()
+            }
+        });
+        assert_eq!(output_delimiter, "\t");
+
+        let re = parse_regex_delim(&cli.delimiter).unwrap();
+        let record = StringRecord::from(re.split("foo   bar  baz").collect::<Vec<_>>());
+        let fields = extract_fields(&record, cli.extract.fields.as_ref().unwrap(), cli.complement);
+        assert_eq!(fields.join(&output_delimiter), "foo\tbaz");
+    }
+
+    #[test]
+    fn test_open_ended_range_via_cli() {
+        let cli = Cli::try_parse_from(["cutr", "-f", "-3", "-"]).unwrap();
+        assert_eq!(cli.extract.fields, Some(vec![PosRange::To(3)]));
+
+        let cli = Cli::try_parse_from(["cutr", "-f", "2-", "-"]).unwrap();
+        assert_eq!(cli.extract.fields, Some(vec![PosRange::From(1)]));
+    }
+
     #[test]
     fn test_parse_pos() {
         assert!(parse_pos("").is_err());
@@ -223,6 +474,7 @@ mod unit_tests {
 
         let res = parse_pos("-");
         assert!(res.is_err());
+        assert_eq!(res.unwrap_err().to_string(), "illegal list value: \"-\"");
 
         let res = parse_pos(",");
         assert!(res.is_err());
@@ -230,9 +482,6 @@ mod unit_tests {
         let res = parse_pos("1,");
         assert!(res.is_err());
 
-        let res = parse_pos("1-");
-        assert!(res.is_err());
-
         let res = parse_pos("1-1-1");
         assert!(res.is_err());
 
@@ -255,70 +504,174 @@ mod unit_tests {
 
         let res = parse_pos("1");
         assert!(res.is_ok());
-        assert_eq!(res.unwrap(), vec![0..1]);
+        assert_eq!(res.unwrap(), vec![PosRange::Closed(0..1)]);
 
         let res = parse_pos("01");
         assert!(res.is_ok());
-        assert_eq!(res.unwrap(), vec![0..1]);
+        assert_eq!(res.unwrap(), vec![PosRange::Closed(0..1)]);
 
         let res = parse_pos("1,3");
         assert!(res.is_ok());
-        assert_eq!(res.unwrap(), vec![0..1, 2..3]);
+        assert_eq!(
+            res.unwrap(),
+            vec![PosRange::Closed(0..1), PosRange::Closed(2..3)]
+        );
 
         let res = parse_pos("001,0003");
         assert!(res.is_ok());
-        assert_eq!(res.unwrap(), vec![0..1, 2..3]);
+        assert_eq!(
+            res.unwrap(),
+            vec![PosRange::Closed(0..1), PosRange::Closed(2..3)]
+        );
 
         let res = parse_pos("1-3");
         assert!(res.is_ok());
-        assert_eq!(res.unwrap(), vec![0..3]);
+        assert_eq!(res.unwrap(), vec![PosRange::Closed(0..3)]);
 
         let res = parse_pos("0001-03");
         assert!(res.is_ok());
-        assert_eq!(res.unwrap(), vec![0..3]);
+        assert_eq!(res.unwrap(), vec![PosRange::Closed(0..3)]);
 
         let res = parse_pos("1,7,3-5");
         assert!(res.is_ok());
-        assert_eq!(res.unwrap(), vec![0..1, 6..7, 2..5]);
+        assert_eq!(
+            res.unwrap(),
+            vec![
+                PosRange::Closed(0..1),
+                PosRange::Closed(6..7),
+                PosRange::Closed(2..5),
+            ]
+        );
 
         let res = parse_pos("15,19-20");
         assert!(res.is_ok());
-        assert_eq!(res.unwrap(), vec![14..15, 18..20]);
+        assert_eq!(
+            res.unwrap(),
+            vec![PosRange::Closed(14..15), PosRange::Closed(18..20)]
+        );
+
+        let res = parse_pos("2-");
+        assert!(res.is_ok());
+        assert_eq!(res.unwrap(), vec![PosRange::From(1)]);
+
+        let res = parse_pos("-3");
+        assert!(res.is_ok());
+        assert_eq!(res.unwrap(), vec![PosRange::To(3)]);
+
+        let res = parse_pos("1,3-");
+        assert!(res.is_ok());
+        assert_eq!(
+            res.unwrap(),
+            vec![PosRange::Closed(0..1), PosRange::From(2)]
+        );
     }
 
     #[test]
     fn test_extract_chars() {
-        assert_eq!(extract_chars("", &[0..1]), "".to_string());
-        assert_eq!(extract_chars("ábc", &[0..1]), "á".to_string());
-        assert_eq!(extract_chars("ábc", &[0..1, 2..3]), "ác".to_string());
-        assert_eq!(extract_chars("ábc", &[0..3]), "ábc".to_string());
-        assert_eq!(extract_chars("ábc", &[2..3, 1..2]), "cb".to_string());
+        assert_eq!(extract_chars("", &[PosRange::Closed(0..1)], false), "".to_string());
+        assert_eq!(extract_chars("ábc", &[PosRange::Closed(0..1)], false), "á".to_string());
         assert_eq!(
-            extract_chars("ábc", &[0..1, 1..2, 4..5]),
+            extract_chars("ábc", &[PosRange::Closed(0..1), PosRange::Closed(2..3)], false),
+            "ác".to_string()
+        );
+        assert_eq!(extract_chars("ábc", &[PosRange::Closed(0..3)], false), "ábc".to_string());
+        assert_eq!(
+            extract_chars("ábc", &[PosRange::Closed(2..3), PosRange::Closed(1..2)], false),
+            "cb".to_string()
+        );
+        assert_eq!(
+            extract_chars("ábc", &[PosRange::Closed(0..1), PosRange::Closed(1..2), PosRange::Closed(4..5)], false),
             "áb".to_string()
         );
+        assert_eq!(extract_chars("ábc", &[PosRange::From(1)], false), "bc".to_string());
+        assert_eq!(extract_chars("ábc", &[PosRange::To(2)], false), "áb".to_string());
+        assert_eq!(extract_chars("ábc", &[PosRange::Closed(0..1)], true), "bc".to_string());
+        assert_eq!(extract_chars("ábc", &[PosRange::Closed(1..2)], true), "ác".to_string());
+        assert_eq!(extract_chars("ábc", &[PosRange::From(1)], true), "á".to_string());
     }
 
     #[test]
-    fn test_extract_bytes() {
-        assert_eq!(extract_bytes("ábc", &[0..1]), "�".to_string());
-        assert_eq!(extract_bytes("ábc", &[0..2]), "á".to_string());
-        assert_eq!(extract_bytes("ábc", &[0..3]), "áb".to_string());
-        assert_eq!(extract_bytes("ábc", &[0..4]), "ábc".to_string());
-        assert_eq!(extract_bytes("ábc", &[3..4, 2..3]), "cb".to_string());
-        assert_eq!(extract_bytes("ábc", &[0..2, 5..6]), "á".to_string());
+    fn test_extract_bytes_raw() {
+        let abc = "ábc".as_bytes();
+        assert_eq!(extract_bytes_raw(abc, &[PosRange::Closed(0..1)], false), &[0xc3]);
+        assert_eq!(extract_bytes_raw(abc, &[PosRange::Closed(0..2)], false), "á".as_bytes());
+        assert_eq!(extract_bytes_raw(abc, &[PosRange::Closed(0..3)], false), "áb".as_bytes());
+        assert_eq!(extract_bytes_raw(abc, &[PosRange::Closed(0..4)], false), "ábc".as_bytes());
+        assert_eq!(
+            extract_bytes_raw(abc, &[PosRange::Closed(3..4), PosRange::Closed(2..3)], false),
+            b"cb"
+        );
+        assert_eq!(
+            extract_bytes_raw(abc, &[PosRange::Closed(0..2), PosRange::Closed(5..6)], false),
+            "á".as_bytes()
+        );
+        assert_eq!(extract_bytes_raw(abc, &[PosRange::From(2)], false), b"bc");
+        assert_eq!(extract_bytes_raw(abc, &[PosRange::To(2)], false), "á".as_bytes());
+        assert_eq!(extract_bytes_raw(abc, &[PosRange::Closed(0..2)], true), b"bc");
+
+        // Invalid UTF-8 (a 0xff byte, which never occurs in UTF-8) must round-trip untouched.
+        let invalid = [0x61, 0xff, 0x62];
+        assert_eq!(
+            extract_bytes_raw(&invalid, &[PosRange::Closed(0..3)], false),
+            vec![0x61, 0xff, 0x62]
+        );
+    }
+
+    #[test]
+    fn test_is_delimited() {
+        assert!(!is_delimited(&StringRecord::from(vec!["Captain"])));
+        assert!(is_delimited(&StringRecord::from(vec!["Captain", "Sham"])));
+    }
+
+    #[test]
+    fn test_ragged_fields_are_parsed_with_flexible_reader() {
+        let input = "a:b\nnodelim\nc:d\n";
+        let read_records = || {
+            ReaderBuilder::new()
+                .has_headers(false)
+                .delimiter(b':')
+                .flexible(true)
+                .from_reader(std::io::Cursor::new(input))
+                .records()
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap()
+        };
+
+        // Mirrors `run`'s field-extraction loop: without -s every record (even
+        // an undelimited one) is kept, with -s only delimited records survive.
+        let only_delimited = false;
+        let kept: Vec<_> = read_records()
+            .into_iter()
+            .filter(|record| !only_delimited || is_delimited(record))
+            .map(|record| extract_fields(&record, &[PosRange::Closed(0..1)], false).join(":"))
+            .collect();
+        assert_eq!(kept, vec!["a", "nodelim", "c"]);
+
+        let only_delimited = true;
+        let kept: Vec<_> = read_records()
+            .into_iter()
+            .filter(|record| !only_delimited || is_delimited(record))
+            .map(|record| extract_fields(&record, &[PosRange::Closed(0..1)], false).join(":"))
+            .collect();
+        assert_eq!(kept, vec!["a", "c"]);
     }
 
     #[test]
     fn test_extract_fields() {
         let rec = StringRecord::from(vec!["Captain", "Sham", "12345"]);
-        assert_eq!(extract_fields(&rec, &[0..1]), &["Captain"]);
-        assert_eq!(extract_fields(&rec, &[1..2]), &["Sham"]);
+        assert_eq!(extract_fields(&rec, &[PosRange::Closed(0..1)], false), &["Captain"]);
+        assert_eq!(extract_fields(&rec, &[PosRange::Closed(1..2)], false), &["Sham"]);
         assert_eq!(
-            extract_fields(&rec, &[0..1, 2..3]),
+            extract_fields(&rec, &[PosRange::Closed(0..1), PosRange::Closed(2..3)], false),
             &["Captain", "12345"]
         );
-        assert_eq!(extract_fields(&rec, &[0..1, 3..4]), &["Captain"]);
-        assert_eq!(extract_fields(&rec, &[1..2, 0..1]), &["Sham", "Captain"]);
+        assert_eq!(extract_fields(&rec, &[PosRange::Closed(0..1), PosRange::Closed(3..4)], false), &["Captain"]);
+        assert_eq!(
+            extract_fields(&rec, &[PosRange::Closed(1..2), PosRange::Closed(0..1)], false),
+            &["Sham", "Captain"]
+        );
+        assert_eq!(extract_fields(&rec, &[PosRange::From(1)], false), &["Sham", "12345"]);
+        assert_eq!(extract_fields(&rec, &[PosRange::To(2)], false), &["Captain", "Sham"]);
+        assert_eq!(extract_fields(&rec, &[PosRange::Closed(1..2)], true), &["Captain", "12345"]);
     }
 }